@@ -0,0 +1,159 @@
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use tauri::Emitter;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Runs a command to completion and returns its combined output. Kept for
+/// one-shot agent actions (a quick `ls`, `cat`, etc.) that don't need a
+/// live, interactive session - use the terminal subsystem below for
+/// anything long-running, interactive, or TUI-based.
+pub fn run_command(command: &str) -> Result<String, String> {
+    let output = std::process::Command::new("bash")
+        .arg("-lc")
+        .arg(command)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(combined)
+}
+
+struct TerminalSession {
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn Child + Send + Sync>,
+}
+
+#[derive(Default)]
+pub struct TerminalManager {
+    sessions: Arc<Mutex<HashMap<String, TerminalSession>>>,
+}
+
+impl TerminalManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns the user's shell in a PTY and streams its raw output to the
+    /// frontend as `terminal:data` events, the same way voice/response
+    /// events already flow. `mode` ("computer"/"browser") is accepted but not
+    /// stored or used: both modes launch the same shell.
+    pub async fn open(
+        &self,
+        _mode: String,
+        app_handle: tauri::AppHandle,
+    ) -> Result<String, String> {
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| e.to_string())?;
+
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
+
+        let child = pair
+            .slave
+            .spawn_command(CommandBuilder::new(shell))
+            .map_err(|e| e.to_string())?;
+        drop(pair.slave);
+
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| e.to_string())?;
+        let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
+
+        let id = Uuid::new_v4().to_string();
+
+        // register the session before the reader starts, so a shell that
+        // exits immediately is still found and reaped by the reader below
+        self.sessions.lock().await.insert(
+            id.clone(),
+            TerminalSession {
+                master: pair.master,
+                writer,
+                child,
+            },
+        );
+
+        let reader_id = id.clone();
+        let sessions_for_reader = self.sessions.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let chunk = String::from_utf8_lossy(&buf[..n]).to_string();
+                        let _ = app_handle.emit(
+                            "terminal:data",
+                            serde_json::json!({ "id": reader_id, "data": chunk }),
+                        );
+                    }
+                    Err(_) => break,
+                }
+            }
+            // the shell exited on its own (e.g. the user typed `exit`) rather
+            // than through `close()`/`close_all()` - reap the child and drop the
+            // bookkeeping entry here too, or the process stays a zombie and the
+            // PTY fds leak for the rest of the app's life
+            if let Some(mut session) = sessions_for_reader.blocking_lock().remove(&reader_id) {
+                let _ = session.child.wait();
+            }
+            let _ = app_handle.emit(
+                "terminal:exit",
+                serde_json::json!({ "id": reader_id }),
+            );
+        });
+
+        Ok(id)
+    }
+
+    pub async fn write(&self, id: &str, data: &[u8]) -> Result<(), String> {
+        let mut sessions = self.sessions.lock().await;
+        let session = sessions.get_mut(id).ok_or("no such terminal session")?;
+        session.writer.write_all(data).map_err(|e| e.to_string())?;
+        session.writer.flush().map_err(|e| e.to_string())
+    }
+
+    pub async fn resize(&self, id: &str, cols: u16, rows: u16) -> Result<(), String> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions.get(id).ok_or("no such terminal session")?;
+        session
+            .master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn close(&self, id: &str) -> Result<(), String> {
+        let mut sessions = self.sessions.lock().await;
+        if let Some(mut session) = sessions.remove(id) {
+            session.child.kill().map_err(|e| e.to_string())?;
+            session.child.wait().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    pub async fn close_all(&self) {
+        let mut sessions = self.sessions.lock().await;
+        for (_, mut session) in sessions.drain() {
+            let _ = session.child.kill();
+            let _ = session.child.wait();
+        }
+    }
+}
+
+pub type SharedTerminalManager = Arc<TerminalManager>;
@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{LogicalPosition, LogicalSize, Manager, WebviewUrl};
+
+const EMBEDDED_LABEL: &str = "embedded-browser";
+
+fn profile_dir() -> PathBuf {
+    let mut dir = dirs::data_dir().unwrap_or_else(std::env::temp_dir);
+    dir.push("omar-ai");
+    dir.push("browser-profile");
+    dir
+}
+
+fn chrome_binary() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"
+    } else if cfg!(target_os = "windows") {
+        "chrome"
+    } else {
+        "google-chrome"
+    }
+}
+
+pub async fn open_profile_dir() -> Result<(), String> {
+    let dir = profile_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    open::that(&dir).map_err(|e| e.to_string())
+}
+
+/// Falls back to driving an external Chromium profile when embedded
+/// webviews aren't available on the platform, or when the caller explicitly
+/// wants the browser outside the assistant overlay.
+pub async fn open_profile_url(url: &str) -> Result<(), String> {
+    std::fs::create_dir_all(profile_dir()).map_err(|e| e.to_string())?;
+    std::process::Command::new(chrome_binary())
+        .arg(format!("--user-data-dir={}", profile_dir().display()))
+        .arg(url)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn reset_profile() -> Result<(), String> {
+    let dir = profile_dir();
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())
+}
+
+pub fn get_profile_status() -> serde_json::Value {
+    serde_json::json!({
+        "exists": profile_dir().exists(),
+        "path": profile_dir().to_string_lossy(),
+        "embeddedAvailable": embedded_webviews_supported(),
+    })
+}
+
+fn chrome_cookies_db_path() -> PathBuf {
+    profile_dir().join("Default").join("Cookies")
+}
+
+/// Clears cookies in the external Chromium profile's sqlite store, and - if
+/// the embedded webview is currently showing that same domain - also
+/// expires them there via `cookie_bridge::EXPIRE_ALL_COOKIES_JS`, so both
+/// surfaces end up logged out together.
+pub async fn clear_domain_cookies(domain: &str, app_handle: Option<&tauri::AppHandle>) -> Result<(), String> {
+    let cookies_db = chrome_cookies_db_path();
+    if cookies_db.exists() {
+        let conn = rusqlite::Connection::open(&cookies_db).map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM cookies WHERE host_key LIKE ?1",
+            [format!("%{}", domain)],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(webview) = embedded_webview_showing_domain(app_handle, domain) {
+        let _ = webview.eval(crate::cookie_bridge::EXPIRE_ALL_COOKIES_JS);
+    }
+    Ok(())
+}
+
+fn embedded_webview_showing_domain(
+    app_handle: Option<&tauri::AppHandle>,
+    domain: &str,
+) -> Option<tauri::Webview> {
+    let webview = app_handle?
+        .get_webview_window("main")?
+        .get_webview(EMBEDDED_LABEL)?;
+    let current_url = webview.url().ok()?;
+    let host = current_url.host_str()?;
+    if host.ends_with(domain) {
+        Some(webview)
+    } else {
+        None
+    }
+}
+
+/// The embedded webview's own persistent storage directory. Deliberately
+/// separate from `profile_dir()` (laid out as a Chrome `--user-data-dir`,
+/// which WebView2/WebKitGTK/WKWebView can't read natively) - cross-engine
+/// session state instead travels through `cookie_bridge`, which exports the
+/// external profile's decrypted cookies and injects them into the embedded
+/// view on navigate.
+fn embedded_profile_dir() -> PathBuf {
+    let mut dir = profile_dir();
+    dir.push("embedded");
+    dir
+}
+
+/// Real runtime check, not a platform guess: macOS and Linux ship
+/// WKWebView/WebKitGTK as part of the OS, so embedded webviews are always
+/// usable there, but Windows' WebView2 is a separate, optionally-installed
+/// component - probe for its runtime before claiming support, so the
+/// "fall back to the external-profile path" behavior this flag gates is
+/// actually reachable.
+pub fn embedded_webviews_supported() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        webview2_runtime_present()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        true
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn webview2_runtime_present() -> bool {
+    std::path::Path::new(r"C:\Program Files (x86)\Microsoft\EdgeWebView\Application").exists()
+        || std::path::Path::new(r"C:\Program Files\Microsoft\EdgeWebView\Application").exists()
+}
+
+// tracks the embedded webview's last position/size so we can keep it glued
+// to the same spot in the host panel after a scroll or window move
+static EMBEDDED_BOUNDS: Mutex<Option<(LogicalPosition<f64>, LogicalSize<f64>)>> = Mutex::new(None);
+
+/// Creates the embedded child webview inside the main panel. Its own
+/// storage directory (see `embedded_profile_dir`) is a separate engine
+/// from the external Chromium profile, so logged-in state is carried over
+/// explicitly via `cookie_bridge` right after the webview is created (not
+/// after its page has finished loading) instead of by
+/// pointing both at the same on-disk profile (which the two engines can't
+/// actually agree on the format of).
+pub fn create_embedded_browser(
+    app_handle: &tauri::AppHandle,
+    url: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) -> Result<(), String> {
+    let main_window = app_handle
+        .get_webview_window("main")
+        .ok_or("main window not found")?;
+
+    if main_window.get_webview(EMBEDDED_LABEL).is_some() {
+        return reposition_embedded_browser(app_handle, x, y, width, height);
+    }
+
+    let position = LogicalPosition::new(x, y);
+    let size = LogicalSize::new(width, height);
+
+    let webview = main_window
+        .add_child(
+            tauri::webview::WebviewBuilder::new(EMBEDDED_LABEL, WebviewUrl::External(
+                url.parse().map_err(|e| format!("{}", e))?,
+            ))
+            .data_directory(embedded_profile_dir()),
+            position,
+            size,
+        )
+        .map_err(|e| e.to_string())?;
+
+    bridge_cookies_into_embedded(&webview, &url);
+    *EMBEDDED_BOUNDS.lock().unwrap() = Some((position, size));
+    Ok(())
+}
+
+pub fn navigate_embedded_browser(app_handle: &tauri::AppHandle, url: String) -> Result<(), String> {
+    let main_window = app_handle
+        .get_webview_window("main")
+        .ok_or("main window not found")?;
+    let webview = main_window
+        .get_webview(EMBEDDED_LABEL)
+        .ok_or("embedded browser not open")?;
+    webview
+        .navigate(url.parse().map_err(|e| format!("{}", e))?)
+        .map_err(|e| e.to_string())?;
+    bridge_cookies_into_embedded(&webview, &url);
+    Ok(())
+}
+
+/// Exports the external profile's decrypted cookies for `url`'s host and
+/// injects them into the embedded webview, so a login made through
+/// `open_profile_url` carries over instead of leaving the embedded view
+/// logged out.
+fn bridge_cookies_into_embedded(webview: &tauri::Webview, url: &str) {
+    let Ok(parsed) = url::Url::parse(url) else { return };
+    let Some(host) = parsed.host_str() else { return };
+    let cookies = crate::cookie_bridge::export_cookies_for_domain(&chrome_cookies_db_path(), host);
+    if cookies.is_empty() {
+        return;
+    }
+    let _ = webview.eval(&crate::cookie_bridge::to_injection_script(&cookies));
+}
+
+/// Re-anchors the child webview, used both when the user scrolls the host
+/// panel's content and when `main`'s window-moved event fires - the classic
+/// "child webview doesn't follow" problem multiwebview setups run into.
+pub fn reposition_embedded_browser(
+    app_handle: &tauri::AppHandle,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) -> Result<(), String> {
+    let main_window = app_handle
+        .get_webview_window("main")
+        .ok_or("main window not found")?;
+    let webview = main_window
+        .get_webview(EMBEDDED_LABEL)
+        .ok_or("embedded browser not open")?;
+
+    let position = LogicalPosition::new(x, y);
+    let size = LogicalSize::new(width, height);
+    webview.set_position(position).map_err(|e| e.to_string())?;
+    webview.set_size(size).map_err(|e| e.to_string())?;
+    *EMBEDDED_BOUNDS.lock().unwrap() = Some((position, size));
+    Ok(())
+}
+
+pub fn close_embedded_browser(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let main_window = app_handle
+        .get_webview_window("main")
+        .ok_or("main window not found")?;
+    if let Some(webview) = main_window.get_webview(EMBEDDED_LABEL) {
+        webview.close().map_err(|e| e.to_string())?;
+    }
+    *EMBEDDED_BOUNDS.lock().unwrap() = None;
+    Ok(())
+}
+
+/// Keeps the embedded webview glued to its last bounds after the host
+/// window moves - call this from the main window's `Moved` event handler.
+pub fn on_host_window_moved(app_handle: &tauri::AppHandle) {
+    if let Some((position, size)) = *EMBEDDED_BOUNDS.lock().unwrap() {
+        let _ = reposition_embedded_browser(app_handle, position.x, position.y, size.width, size.height);
+    }
+}
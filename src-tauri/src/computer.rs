@@ -0,0 +1,30 @@
+use base64::Engine;
+use screenshots::Screen;
+
+pub struct ComputerControl {
+    screen: Screen,
+}
+
+impl ComputerControl {
+    pub fn new() -> Result<Self, String> {
+        let screen = Screen::all()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .next()
+            .ok_or("no screen found")?;
+        Ok(Self { screen })
+    }
+
+    /// Captures the primary screen and returns raw PNG bytes.
+    pub fn take_screenshot_png(&self) -> Result<Vec<u8>, String> {
+        let image = self.screen.capture().map_err(|e| e.to_string())?;
+        image.to_png(None).map_err(|e| e.to_string())
+    }
+
+    /// Base64-encoded PNG, for callers that genuinely need the bytes inline
+    /// (e.g. sending a frame to the model) rather than an `omar-shot://` id.
+    pub fn take_screenshot(&self) -> Result<String, String> {
+        let bytes = self.take_screenshot_png()?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+}
@@ -0,0 +1,138 @@
+use aes::Aes128;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use pbkdf2::pbkdf2_hmac;
+use rusqlite::Connection;
+use sha1::Sha1;
+use std::path::Path;
+
+type Aes128CbcDec = cbc::Decryptor<Aes128>;
+
+#[derive(Debug, Clone)]
+pub struct BridgedCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub secure: bool,
+}
+
+/// Reads and decrypts the cookies Chrome has for `domain` out of the
+/// external profile's `Cookies` sqlite db, so the embedded webview can be
+/// handed the same logged-in session `open_profile_url` built up. Returns
+/// an empty list (rather than erroring) when the platform key isn't
+/// available - the embedded view just starts logged out in that case.
+pub fn export_cookies_for_domain(cookies_db: &Path, domain: &str) -> Vec<BridgedCookie> {
+    let Some(key) = decryption_key() else {
+        return Vec::new();
+    };
+    let Ok(conn) = Connection::open(cookies_db) else {
+        return Vec::new();
+    };
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT host_key, name, encrypted_value, path, is_secure FROM cookies WHERE host_key LIKE ?1",
+    ) else {
+        return Vec::new();
+    };
+
+    let rows = stmt.query_map([format!("%{}", domain)], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Vec<u8>>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, bool>(4)?,
+        ))
+    });
+
+    let Ok(rows) = rows else { return Vec::new() };
+
+    rows.filter_map(|row| row.ok())
+        .filter_map(|(host_key, name, encrypted_value, path, is_secure)| {
+            let value = decrypt_cookie_value(&encrypted_value, &key)?;
+            Some(BridgedCookie {
+                name,
+                value,
+                domain: host_key,
+                path,
+                secure: is_secure,
+            })
+        })
+        .collect()
+}
+
+// chrome encrypts cookie values at rest with AES-128-CBC, keyed off a
+// password that's OS-keychain-backed on macOS and a well-known PBKDF2
+// fallback ("peanuts") on Linux when no OS keyring is wired up. Windows
+// protects its key with DPAPI, which needs platform APIs we don't depend
+// on yet, so cookies are skipped there rather than injected wrong.
+#[cfg(target_os = "linux")]
+fn decryption_key() -> Option<[u8; 16]> {
+    Some(derive_key(b"peanuts", 1))
+}
+
+#[cfg(target_os = "macos")]
+fn decryption_key() -> Option<[u8; 16]> {
+    let entry = keyring::Entry::new("Chrome Safe Storage", "Chrome").ok()?;
+    let password = entry.get_password().ok()?;
+    // chrome on macOS derives with 1003 PBKDF2 rounds, not 1
+    Some(derive_key(password.as_bytes(), 1003))
+}
+
+#[cfg(target_os = "windows")]
+fn decryption_key() -> Option<[u8; 16]> {
+    None
+}
+
+fn derive_key(password: &[u8], iterations: u32) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2_hmac::<Sha1>(password, b"saltysalt", iterations, &mut key);
+    key
+}
+
+fn decrypt_cookie_value(encrypted: &[u8], key: &[u8; 16]) -> Option<String> {
+    if encrypted.len() < 3 + 16 {
+        return None;
+    }
+    let version = &encrypted[..3];
+    if version != b"v10" && version != b"v11" {
+        return None;
+    }
+
+    let iv = [b' '; 16];
+    let mut buf = encrypted[3..].to_vec();
+    let decryptor = Aes128CbcDec::new(key.into(), &iv.into());
+    let plaintext = decryptor.decrypt_padded_mut::<Pkcs7>(&mut buf).ok()?;
+    String::from_utf8(plaintext.to_vec()).ok()
+}
+
+/// Best-effort cookie clear for the embedded webview: expires every cookie
+/// visible to the currently-loaded page. Only meaningful when the embedded
+/// view is actually showing the domain being cleared - the caller is
+/// responsible for checking that before evaluating this.
+pub const EXPIRE_ALL_COOKIES_JS: &str = r#"
+document.cookie.split(';').forEach(function (c) {
+    var name = c.split('=')[0].trim();
+    if (name) {
+        document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 UTC; path=/;';
+    }
+});
+"#;
+
+/// JS of `document.cookie` assignments, one per bridged cookie, that
+/// `browser` evaluates in the embedded webview right after create/navigate
+/// is issued. It runs in whichever document is loaded at that moment; it is
+/// not guaranteed to run before the new page's own scripts.
+pub fn to_injection_script(cookies: &[BridgedCookie]) -> String {
+    cookies
+        .iter()
+        .map(|c| {
+            let mut cookie = format!("{}={}; path={}", c.name, c.value, c.path);
+            if c.secure {
+                cookie.push_str("; secure");
+            }
+            format!("document.cookie = {:?};", cookie)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
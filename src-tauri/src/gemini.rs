@@ -4,6 +4,7 @@ use crate::storage::Usage;
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tokio::sync::mpsc;
 
 const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta/openai/chat/completions";
@@ -14,6 +15,33 @@ pub struct GeminiClient {
     model: String,
 }
 
+// نفس تعريفات الأدوات المستخدمة في مسار Anthropic، بصيغة OpenAI function-calling
+fn tools_array(mode: AgentMode) -> Vec<serde_json::Value> {
+    crate::api::tool_schemas(mode)
+        .into_iter()
+        .map(|tool| {
+            serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": tool["name"],
+                    "description": tool["description"],
+                    "parameters": tool["input_schema"],
+                }
+            })
+        })
+        .collect()
+}
+
+// تجميع استدعاء أداة واحد قيد البث عبر عدة chunks - جيميني يرسل id/name مرة
+// واحدة ثم يرسل arguments على دفعات صغيرة، مع تمييزها بحقل index
+#[derive(Default, Clone)]
+struct PendingToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+    started: bool,
+}
+
 impl GeminiClient {
     pub fn new(api_key: String, model: String) -> Self {
         Self {
@@ -30,15 +58,13 @@ impl GeminiClient {
         mode: AgentMode,
         _voice_mode: bool,
     ) -> Result<ApiResult, ApiError> {
-        // تحويل الرسائل إلى تنسيق OpenAI/Gemini
         let mut openai_messages = Vec::new();
-        
-        // إضافة System Prompt
+
         let system_prompt = match mode {
             AgentMode::Computer => crate::api::SYSTEM_PROMPT,
             AgentMode::Browser => crate::api::BROWSER_SYSTEM_PROMPT,
         };
-        
+
         openai_messages.push(serde_json::json!({
             "role": "system",
             "content": system_prompt
@@ -46,6 +72,8 @@ impl GeminiClient {
 
         for msg in messages {
             let mut content_parts = Vec::new();
+            let mut tool_calls = Vec::new();
+
             for block in msg.content {
                 match block {
                     ContentBlock::Text { text } => {
@@ -62,19 +90,91 @@ impl GeminiClient {
                             }
                         }));
                     }
-                    _ => {} // تخطي أنواع الكتل الأخرى للتبسيط في النسخة الأولية
+                    ContentBlock::ToolUse { id, name, input } => {
+                        tool_calls.push(serde_json::json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {
+                                "name": name,
+                                "arguments": input.to_string(),
+                            }
+                        }));
+                    }
+                    ContentBlock::ToolResult { tool_use_id, content, is_error: _ } => {
+                        let mut text_parts = Vec::new();
+                        let mut images = Vec::new();
+                        for c in content {
+                            match c {
+                                ToolResultContent::Text { text } => text_parts.push(text),
+                                ToolResultContent::Image { source } => images.push(source),
+                            }
+                        }
+
+                        // the OpenAI-compatible `tool` role only carries a string body, so
+                        // the text (or a placeholder, if the result was just a screenshot)
+                        // goes there and any image rides along in a follow-up user message
+                        // the model can actually see - dropping it would leave Gemini unable
+                        // to drive the computer, which is the whole point of this mode
+                        let text = if text_parts.is_empty() {
+                            "[see attached screenshot]".to_string()
+                        } else {
+                            text_parts.join("\n")
+                        };
+                        openai_messages.push(serde_json::json!({
+                            "role": "tool",
+                            "tool_call_id": tool_use_id,
+                            "content": text,
+                        }));
+
+                        if !images.is_empty() {
+                            let image_parts: Vec<_> = images
+                                .into_iter()
+                                .map(|source| {
+                                    serde_json::json!({
+                                        "type": "image_url",
+                                        "image_url": {
+                                            "url": format!("data:{};base64,{}", source.media_type, source.data)
+                                        }
+                                    })
+                                })
+                                .collect();
+                            openai_messages.push(serde_json::json!({
+                                "role": "user",
+                                "content": image_parts,
+                            }));
+                        }
+                    }
                 }
             }
-            openai_messages.push(serde_json::json!({
-                "role": msg.role,
-                "content": content_parts
-            }));
+
+            if !tool_calls.is_empty() {
+                // a turn can carry both prose and a tool call (exactly what this
+                // client's own streaming loop below produces) - keep both instead
+                // of nulling the text out, or the next turn loses what was said
+                let content = if content_parts.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::Value::Array(content_parts)
+                };
+                openai_messages.push(serde_json::json!({
+                    "role": msg.role,
+                    "content": content,
+                    "tool_calls": tool_calls,
+                }));
+            } else if !content_parts.is_empty() {
+                openai_messages.push(serde_json::json!({
+                    "role": msg.role,
+                    "content": content_parts
+                }));
+            }
         }
 
         let request_body = serde_json::json!({
-            "model": "gemini-2.0-flash", // استخدام الموديل المتاح حالياً كبديل لـ 2.5 إذا لم يتوفر
+            "model": self.model,
             "messages": openai_messages,
+            "tools": tools_array(mode),
             "stream": true,
+            "stream_options": { "include_usage": true },
             "max_tokens": 4096
         });
 
@@ -94,38 +194,94 @@ impl GeminiClient {
         let mut stream = response.bytes_stream();
         let mut content_blocks = Vec::new();
         let mut full_text = String::new();
+        let mut pending_tool_calls: HashMap<u64, PendingToolCall> = HashMap::new();
+        let mut usage = Usage {
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_input_tokens: None,
+            cache_read_input_tokens: None,
+        };
 
         while let Some(item) = stream.next().await {
             let chunk = item?;
             let text = String::from_utf8_lossy(&chunk);
-            
+
             for line in text.lines() {
                 if line.starts_with("data: ") {
                     let data = &line[6..];
                     if data == "[DONE]" { break; }
-                    
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
-                        if let Some(content) = json["choices"][0]["delta"]["content"].as_str() {
-                            full_text.push_str(content);
-                            let _ = event_tx.send(StreamEvent::TextDelta {
-                                text: content.to_string(),
-                            });
+
+                    let Ok(json) = serde_json::from_str::<serde_json::Value>(data) else { continue };
+
+                    if let Some(content) = json["choices"][0]["delta"]["content"].as_str() {
+                        full_text.push_str(content);
+                        let _ = event_tx.send(StreamEvent::TextDelta {
+                            text: content.to_string(),
+                        });
+                    }
+
+                    if let Some(tool_calls) = json["choices"][0]["delta"]["tool_calls"].as_array() {
+                        for tc in tool_calls {
+                            let index = tc["index"].as_u64().unwrap_or(0);
+                            let entry = pending_tool_calls.entry(index).or_default();
+
+                            if let Some(id) = tc["id"].as_str() {
+                                entry.id = id.to_string();
+                            }
+                            if let Some(name) = tc["function"]["name"].as_str() {
+                                entry.name = name.to_string();
+                            }
+                            if let Some(args) = tc["function"]["arguments"].as_str() {
+                                entry.arguments.push_str(args);
+                            }
+
+                            if !entry.started && !entry.id.is_empty() && !entry.name.is_empty() {
+                                entry.started = true;
+                                let _ = event_tx.send(StreamEvent::ToolUseStart {
+                                    id: entry.id.clone(),
+                                    name: entry.name.clone(),
+                                });
+                            }
+                            if entry.started {
+                                if let Some(args) = tc["function"]["arguments"].as_str() {
+                                    let _ = event_tx.send(StreamEvent::ToolUseInputDelta {
+                                        id: entry.id.clone(),
+                                        partial_json: args.to_string(),
+                                    });
+                                }
+                            }
                         }
                     }
+
+                    if let Some(prompt_tokens) = json["usage"]["prompt_tokens"].as_u64() {
+                        usage.input_tokens = prompt_tokens as u32;
+                    }
+                    if let Some(completion_tokens) = json["usage"]["completion_tokens"].as_u64() {
+                        usage.output_tokens = completion_tokens as u32;
+                    }
                 }
             }
         }
 
-        content_blocks.push(ContentBlock::Text { text: full_text });
+        if !full_text.is_empty() {
+            content_blocks.push(ContentBlock::Text { text: full_text });
+        }
+
+        let mut calls: Vec<_> = pending_tool_calls.into_iter().collect();
+        calls.sort_by_key(|(index, _)| *index);
+        for (_, call) in calls {
+            let input = serde_json::from_str(&call.arguments).unwrap_or(serde_json::json!({}));
+            let _ = event_tx.send(StreamEvent::ToolUseStop { id: call.id.clone() });
+            content_blocks.push(ContentBlock::ToolUse {
+                id: call.id,
+                name: call.name,
+                input,
+            });
+        }
 
         Ok(ApiResult {
             content: content_blocks,
-            usage: Usage {
-                input_tokens: 0,
-                output_tokens: 0,
-                cache_creation_input_tokens: None,
-                cache_read_input_tokens: None,
-            },
+            usage,
         })
     }
 }
@@ -9,10 +9,12 @@ mod agent;
 mod api;
 mod bash;
 mod browser;
+mod cookie_bridge;
 mod computer;
 mod gemini;
 mod panels;
 mod permissions;
+mod screenshot_cache;
 mod storage;
 mod voice;
 
@@ -44,6 +46,7 @@ tauri_panel! {
 struct AppState {
     agent: Arc<Mutex<Agent>>,
     running: Arc<std::sync::atomic::AtomicBool>,
+    terminals: bash::SharedTerminalManager,
 }
 
 // cached screen info for fast window positioning
@@ -195,6 +198,7 @@ fn set_window_state(app_handle: tauri::AppHandle, width: f64, height: f64, cente
     {
         if let Some(window) = app_handle.get_webview_window("main") {
             let _ = window.set_size(tauri::LogicalSize::new(width, height));
+            panels::float_on_all_workspaces(&window);
             let _ = window.show();
         }
     }
@@ -217,6 +221,7 @@ fn show_voice_window(app_handle: tauri::AppHandle) -> Result<(), String> {
     {
         if let Some(window) = app_handle.get_webview_window("voice") {
             let _ = window.center();
+            panels::float_on_all_workspaces(&window);
             let _ = window.show();
         }
     }
@@ -283,34 +288,45 @@ fn set_main_click_through(ignore: bool) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn show_border_overlay() {
+fn show_border_overlay(app_handle: tauri::AppHandle) {
     #[cfg(target_os = "macos")]
     if let Some(panel) = BORDER_PANEL.get() {
         panel.show();
     }
+    #[cfg(not(target_os = "macos"))]
+    if let Some(window) = app_handle.get_webview_window("border") {
+        panels::float_on_all_workspaces(&window);
+        let _ = window.show();
+    }
 }
 
 #[tauri::command]
-fn hide_border_overlay() {
+fn hide_border_overlay(_app_handle: tauri::AppHandle) {
     #[cfg(target_os = "macos")]
     if let Some(panel) = BORDER_PANEL.get() {
         panel.hide();
     }
+    #[cfg(not(target_os = "macos"))]
+    if let Some(window) = _app_handle.get_webview_window("border") {
+        let _ = window.hide();
+    }
 }
 
-// take screenshot excluding our app windows - uses shared panels module
+// take screenshot excluding our app windows - uses shared panels module.
+// returns an `omar-shot://<id>` url the webview can point an <img> at
+// instead of a base64 blob through IPC.
 #[tauri::command]
 fn take_screenshot_excluding_app() -> Result<String, String> {
     #[cfg(target_os = "macos")]
-    {
-        panels::take_screenshot_excluding_app()
-    }
+    let bytes = panels::take_screenshot_excluding_app_png()?;
 
     #[cfg(not(target_os = "macos"))]
-    {
+    let bytes = {
         let control = computer::ComputerControl::new().map_err(|e| e.to_string())?;
-        control.take_screenshot().map_err(|e| e.to_string())
-    }
+        control.take_screenshot_png()?
+    };
+
+    Ok(format!("omar-shot://{}", screenshot_cache::store(bytes)))
 }
 
 // trigger screen flash effect - plays sound as feedback
@@ -322,16 +338,27 @@ fn trigger_screen_flash() {
         .ok();
 }
 
-// hotkey triggered - capture screenshot and return base64
+// hotkey triggered - capture screenshot, return an `omar-shot://<id>` url
 #[tauri::command]
 fn capture_screen_for_help() -> Result<String, String> {
     let control = computer::ComputerControl::new().map_err(|e| e.to_string())?;
-    let screenshot = control.take_screenshot().map_err(|e| e.to_string())?;
-    
+    let bytes = control.take_screenshot_png().map_err(|e| e.to_string())?;
+
     #[cfg(target_os = "macos")]
     trigger_screen_flash();
 
-    Ok(screenshot)
+    Ok(format!("omar-shot://{}", screenshot_cache::store(bytes)))
+}
+
+// base64 fallback for callers that need the bytes inline, e.g. sending a
+// frame to the model rather than rendering it in the webview
+#[tauri::command]
+fn get_screenshot_base64(id: String) -> Result<String, String> {
+    let bytes = screenshot_cache::get(&id).ok_or("screenshot not found or evicted")?;
+    Ok(base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        bytes,
+    ))
 }
 
 #[tauri::command]
@@ -380,8 +407,46 @@ fn get_browser_profile_status() -> Result<serde_json::Value, String> {
 }
 
 #[tauri::command]
-async fn clear_domain_cookies(domain: String) -> Result<(), String> {
-    browser::clear_domain_cookies(&domain).await.map_err(|e| e.to_string())
+async fn clear_domain_cookies(domain: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    browser::clear_domain_cookies(&domain, Some(&app_handle))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn create_embedded_browser(
+    url: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    if !browser::embedded_webviews_supported() {
+        return Err("embedded webviews not supported on this platform".to_string());
+    }
+    browser::create_embedded_browser(&app_handle, url, x, y, width, height)
+}
+
+#[tauri::command]
+fn navigate_embedded_browser(url: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    browser::navigate_embedded_browser(&app_handle, url)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn reposition_embedded_browser(
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    browser::reposition_embedded_browser(&app_handle, x, y, width, height)
+}
+
+#[tauri::command]
+fn close_embedded_browser(app_handle: tauri::AppHandle) -> Result<(), String> {
+    browser::close_embedded_browser(&app_handle)
 }
 
 #[tauri::command]
@@ -394,6 +459,13 @@ fn get_api_key_status() -> Result<serde_json::Value, String> {
     Ok(storage::get_api_key_status())
 }
 
+// lets the frontend fall back to a session-only key prompt when no OS secret
+// backend is reachable (e.g. headless Linux with no Secret Service running)
+#[tauri::command]
+fn secret_backend_available() -> Result<bool, String> {
+    Ok(storage::secret_backend_available())
+}
+
 #[tauri::command]
 fn save_voice_settings(voice_id: String) -> Result<(), String> {
     storage::save_voice_settings(&voice_id)
@@ -419,6 +491,30 @@ fn delete_conversation(id: String) -> Result<(), String> {
     storage::delete_conversation(&id)
 }
 
+#[tauri::command]
+async fn open_terminal(
+    mode: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    state.terminals.open(mode, app_handle).await
+}
+
+#[tauri::command]
+async fn write_terminal(id: String, data: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.terminals.write(&id, data.as_bytes()).await
+}
+
+#[tauri::command]
+async fn resize_terminal(id: String, cols: u16, rows: u16, state: State<'_, AppState>) -> Result<(), String> {
+    state.terminals.resize(&id, cols, rows).await
+}
+
+#[tauri::command]
+async fn close_terminal(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.terminals.close(&id).await
+}
+
 fn main() {
     if let Err(e) = storage::init_db() {
         eprintln!("Failed to initialize database: {}", e);
@@ -443,9 +539,23 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_positioner::init())
+        .register_uri_scheme_protocol("omar-shot", |_ctx, request| {
+            let id = request.uri().host().unwrap_or_default();
+            match screenshot_cache::get(id) {
+                Some(bytes) => tauri::http::Response::builder()
+                    .header("Content-Type", "image/png")
+                    .body(bytes)
+                    .unwrap(),
+                None => tauri::http::Response::builder()
+                    .status(tauri::http::StatusCode::NOT_FOUND)
+                    .body(Vec::new())
+                    .unwrap(),
+            }
+        })
         .manage(AppState {
             agent: agent.clone(),
             running: running.clone(),
+            terminals: Arc::new(bash::TerminalManager::new()),
         })
         .setup(move |app| {
             // register global shortcuts
@@ -525,6 +635,28 @@ fn main() {
                 BORDER_PANEL.set(border_panel).ok();
             }
 
+            // windows/linux: pin the overlay windows across virtual desktops
+            // since there's no panel API to lean on here
+            #[cfg(not(target_os = "macos"))]
+            {
+                for label in ["main", "voice", "border"] {
+                    if let Some(window) = app.get_webview_window(label) {
+                        panels::float_on_all_workspaces(&window);
+                    }
+                }
+            }
+
+            // re-anchor the embedded browser child webview when the host
+            // window moves, so it doesn't drift away from the panel
+            if let Some(main_window) = app.get_webview_window("main") {
+                let app_handle = app.handle().clone();
+                main_window.on_window_event(move |event| {
+                    if let tauri::WindowEvent::Moved(_) = event {
+                        browser::on_host_window_moved(&app_handle);
+                    }
+                });
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -544,6 +676,7 @@ fn main() {
             hide_border_overlay,
             take_screenshot_excluding_app,
             capture_screen_for_help,
+            get_screenshot_base64,
             start_ptt,
             stop_ptt,
             check_permissions,
@@ -554,14 +687,30 @@ fn main() {
             reset_browser_profile,
             get_browser_profile_status,
             clear_domain_cookies,
+            create_embedded_browser,
+            navigate_embedded_browser,
+            reposition_embedded_browser,
+            close_embedded_browser,
             save_api_key,
             get_api_key_status,
+            secret_backend_available,
             save_voice_settings,
             get_voice_settings,
             list_conversations,
             load_conversation,
             delete_conversation,
+            open_terminal,
+            write_terminal,
+            resize_terminal,
+            close_terminal,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                let state: State<AppState> = app_handle.state();
+                let terminals = state.terminals.clone();
+                tauri::async_runtime::block_on(terminals.close_all());
+            }
+        });
 }
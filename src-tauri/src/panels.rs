@@ -0,0 +1,28 @@
+use std::sync::OnceLock;
+
+#[cfg(target_os = "macos")]
+use tauri_nspanel::raw_nspanel::RawNSPanel;
+
+#[cfg(target_os = "macos")]
+pub static MAIN_PANEL: OnceLock<RawNSPanel> = OnceLock::new();
+#[cfg(target_os = "macos")]
+pub static VOICE_PANEL: OnceLock<RawNSPanel> = OnceLock::new();
+#[cfg(target_os = "macos")]
+pub static BORDER_PANEL: OnceLock<RawNSPanel> = OnceLock::new();
+
+/// Pins a window across virtual desktops/Spaces and gives it the same
+/// non-activating floating-panel feel everywhere, not just on macOS (where
+/// `CanJoinAllSpaces | FullScreenAuxiliary` already does this). Call this on
+/// every overlay window (main, voice, border) right after it's created so
+/// setup and the window-state commands stay in sync.
+pub fn float_on_all_workspaces(window: &tauri::WebviewWindow) {
+    let _ = window.set_visible_on_all_workspaces(true);
+    let _ = window.set_always_on_top(true);
+    let _ = window.set_skip_taskbar(true);
+}
+
+#[cfg(target_os = "macos")]
+pub fn take_screenshot_excluding_app_png() -> Result<Vec<u8>, String> {
+    let control = crate::computer::ComputerControl::new().map_err(|e| e.to_string())?;
+    control.take_screenshot_png()
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const MAX_ENTRIES: usize = 30;
+const MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+struct Entry {
+    bytes: Vec<u8>,
+    inserted_at: Instant,
+}
+
+#[derive(Default)]
+struct Cache {
+    order: Vec<String>,
+    entries: HashMap<String, Entry>,
+}
+
+static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<Cache> {
+    CACHE.get_or_init(|| Mutex::new(Cache::default()))
+}
+
+/// Stores raw PNG bytes under a fresh id for the `omar-shot://` protocol to
+/// serve. Evicted by age or count so a long session doesn't grow memory
+/// unbounded just because the agent keeps taking screenshots.
+pub fn store(bytes: Vec<u8>) -> String {
+    let id = Uuid::new_v4().to_string();
+    let mut cache = cache().lock().unwrap();
+    cache.entries.insert(
+        id.clone(),
+        Entry {
+            bytes,
+            inserted_at: Instant::now(),
+        },
+    );
+    cache.order.push(id.clone());
+    evict(&mut cache);
+    id
+}
+
+pub fn get(id: &str) -> Option<Vec<u8>> {
+    let mut cache = cache().lock().unwrap();
+    let bytes = cache.entries.get(id).map(|e| e.bytes.clone())?;
+    // bump this id to the back of `order` (most-recently-used) so an actively
+    // re-displayed screenshot doesn't get evicted ahead of an older, unused one
+    if let Some(pos) = cache.order.iter().position(|cached_id| cached_id == id) {
+        let id_owned = cache.order.remove(pos);
+        cache.order.push(id_owned);
+    }
+    Some(bytes)
+}
+
+fn evict(cache: &mut Cache) {
+    let now = Instant::now();
+    let mut i = 0;
+    while i < cache.order.len() {
+        let expired = cache
+            .entries
+            .get(&cache.order[i])
+            .map(|e| now.duration_since(e.inserted_at) >= MAX_AGE)
+            .unwrap_or(true);
+        if expired {
+            let id = cache.order.remove(i);
+            cache.entries.remove(&id);
+        } else {
+            i += 1;
+        }
+    }
+    while cache.order.len() > MAX_ENTRIES {
+        let id = cache.order.remove(0);
+        cache.entries.remove(&id);
+    }
+}
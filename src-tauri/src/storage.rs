@@ -0,0 +1,251 @@
+use keyring::Entry;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+const KEYRING_SERVICE: &str = "com.omar-ai.app";
+const SECRET_SERVICES: [&str; 2] = ["anthropic", "gemini"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Usage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_creation_input_tokens: Option<u32>,
+    pub cache_read_input_tokens: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationMeta {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    pub messages: serde_json::Value,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+static DB: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+fn db_path() -> PathBuf {
+    let mut dir = dirs::data_dir().unwrap_or_else(std::env::temp_dir);
+    dir.push("omar-ai");
+    std::fs::create_dir_all(&dir).ok();
+    dir.push("omar.db");
+    dir
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let conn = DB.get().ok_or("database not initialized")?;
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+    f(&conn).map_err(|e| e.to_string())
+}
+
+fn keyring_entry(service: &str) -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, service).map_err(|e| e.to_string())
+}
+
+/// Returns true if a platform secret backend (Keychain / Credential Manager /
+/// Secret Service) is reachable. Used by the frontend to fall back to
+/// "paste key each session" on headless Linux where no secret service runs.
+pub fn secret_backend_available() -> bool {
+    // allow-list the outcomes that mean "a backend answered" rather than
+    // deny-listing failure variants - on headless Linux with no D-Bus Secret
+    // Service running, keyring reports neither `Ok` nor `NoEntry`, and that's
+    // exactly the case this flag exists to catch
+    let Ok(entry) = keyring_entry("omar-ai-probe") else {
+        return false;
+    };
+    matches!(entry.get_password(), Ok(_) | Err(keyring::Error::NoEntry))
+}
+
+pub fn init_db() -> Result<(), String> {
+    let conn = Connection::open(db_path()).map_err(|e| e.to_string())?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS api_keys (
+            service TEXT PRIMARY KEY,
+            configured INTEGER NOT NULL DEFAULT 0,
+            key TEXT
+        );
+        CREATE TABLE IF NOT EXISTS conversations (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            messages TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+
+    migrate_plaintext_keys_to_vault(&conn)?;
+
+    DB.set(Mutex::new(conn))
+        .map_err(|_| "database already initialized".to_string())?;
+    Ok(())
+}
+
+/// One-time migration: any key still sitting in the `api_keys.key` column from
+/// before the vault existed gets written into the platform secret store and
+/// scrubbed from the DB. `configured` stays set either way so
+/// `get_api_key_status` keeps working off the non-secret flag.
+fn migrate_plaintext_keys_to_vault(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("SELECT service, key FROM api_keys WHERE key IS NOT NULL")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut migrated = Vec::new();
+    for row in rows {
+        let (service, key) = row.map_err(|e| e.to_string())?;
+        migrated.push((service, key));
+    }
+    drop(stmt);
+
+    for (service, key) in migrated {
+        if let Ok(entry) = keyring_entry(&service) {
+            if entry.set_password(&key).is_ok() {
+                conn.execute(
+                    "UPDATE api_keys SET key = NULL WHERE service = ?1",
+                    [&service],
+                )
+                .map_err(|e| e.to_string())?;
+                println!("[storage] migrated {} API key into secret vault", service);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves the key to the platform vault and flips the non-secret `configured`
+/// flag in the DB so `get_api_key_status` doesn't need vault access.
+pub fn save_api_key(service: &str, key: &str) -> Result<(), String> {
+    let entry = keyring_entry(service)?;
+    entry.set_password(key).map_err(|e| e.to_string())?;
+
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO api_keys (service, configured, key) VALUES (?1, 1, NULL)
+             ON CONFLICT(service) DO UPDATE SET configured = 1, key = NULL",
+            [service],
+        )?;
+        Ok(())
+    })
+}
+
+pub fn get_api_key(service: &str) -> Result<Option<String>, String> {
+    let entry = keyring_entry(service)?;
+    match entry.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn get_api_key_status() -> serde_json::Value {
+    let mut status = serde_json::Map::new();
+    for service in SECRET_SERVICES {
+        let configured = with_db(|conn| {
+            conn.query_row(
+                "SELECT configured FROM api_keys WHERE service = ?1",
+                [service],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|v| v != 0)
+            .or(Ok(false))
+        })
+        .unwrap_or(false);
+        status.insert(service.to_string(), serde_json::json!(configured));
+    }
+    status.insert(
+        "secretBackendAvailable".to_string(),
+        serde_json::json!(secret_backend_available()),
+    );
+    serde_json::Value::Object(status)
+}
+
+pub fn save_voice_settings(voice_id: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES ('voice_id', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = ?1",
+            [voice_id],
+        )?;
+        Ok(())
+    })
+}
+
+pub fn get_voice_settings() -> serde_json::Value {
+    let voice_id = with_db(|conn| {
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = 'voice_id'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+    })
+    .ok();
+    serde_json::json!({ "voiceId": voice_id })
+}
+
+pub fn list_conversations(limit: usize, offset: usize) -> Result<Vec<ConversationMeta>, String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, title, created_at, updated_at FROM conversations
+             ORDER BY updated_at DESC LIMIT ?1 OFFSET ?2",
+        )?;
+        let rows = stmt.query_map([limit as i64, offset as i64], |row| {
+            Ok(ConversationMeta {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+pub fn load_conversation(id: &str) -> Result<Option<Conversation>, String> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT id, title, messages, created_at, updated_at FROM conversations WHERE id = ?1",
+            [id],
+            |row| {
+                let messages: String = row.get(2)?;
+                Ok(Conversation {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    messages: serde_json::from_str(&messages).unwrap_or(serde_json::Value::Null),
+                    created_at: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            },
+        )
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e),
+        })
+    })
+}
+
+pub fn delete_conversation(id: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM conversations WHERE id = ?1", [id])?;
+        Ok(())
+    })
+}